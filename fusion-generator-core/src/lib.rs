@@ -0,0 +1,2 @@
+#[path = "ts.rs"]
+pub mod ts_a;
@@ -1,3 +1,5 @@
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
 
 use concat_string::concat_string;
@@ -26,12 +28,43 @@ macro_rules! impl_ast {
   };
 }
 
+/// Deduplicates the `Rc<String>` backing allocations handed out by
+/// `Ident::new`/`Import::new`. Codegen re-creates the same handful of
+/// names (`Array`, `number`, a struct name referenced from every field and
+/// import that uses it) constantly, so sharing one allocation per distinct
+/// string — the interning approach rust-analyzer uses for its symbols —
+/// turns those into a cheap `Rc::clone` instead of a fresh heap string.
+#[derive(Default)]
+struct Interner {
+  strings: HashMap<String, Rc<String>>,
+}
+
+impl Interner {
+  fn intern(&mut self, value: &str) -> Rc<String> {
+    if let Some(existing) = self.strings.get(value) {
+      return Rc::clone(existing);
+    }
+
+    let interned = Rc::new(value.to_string());
+    self.strings.insert(value.to_string(), Rc::clone(&interned));
+    interned
+  }
+}
+
+thread_local! {
+  static INTERNER: RefCell<Interner> = RefCell::new(Interner::default());
+}
+
+fn intern(value: &str) -> Rc<String> {
+  INTERNER.with(|interner| interner.borrow_mut().intern(value))
+}
+
 #[derive(Clone)]
 pub struct Ident(Rc<String>);
 
 impl Ident {
   pub fn new(name: &str) -> Self {
-    Self(Rc::new(name.to_string()))
+    Self(intern(name))
   }
 }
 
@@ -44,30 +77,58 @@ pub struct Import {
 impl Import {
   pub fn new(symbol: Ident, source: &str) -> Self {
     Self {
-      source: Rc::new(source.to_string()),
+      source: intern(source),
       symbol,
     }
   }
 }
 
+/// A named type with optional generic arguments, a union/intersection of
+/// other types, a string-literal type, or any of those made optional.
+/// `Union`/`Intersection` let the translator express Java sealed
+/// hierarchies, and `Literal` gives enum-like values a real type instead
+/// of the enum backend faking them via string formatting.
 #[derive(Clone)]
-pub struct Type {
-  optional: bool,
-  inner: Option<Rc<Vec<Type>>>,
-  name: Ident,
+pub enum Type {
+  Named {
+    name: Ident,
+    generics: Option<Vec<Type>>,
+  },
+  Union(Vec<Type>),
+  Intersection(Vec<Type>),
+  Literal(String),
+  Optional(Box<Type>),
 }
 
 impl Type {
-  pub fn new(name: Ident, optional: bool, inner: Option<Vec<Type>>) -> Self {
-    Self {
-      optional,
-      inner: inner.map(|val| Rc::new(val)),
-      name,
+  pub fn new(name: Ident, optional: bool, generics: Option<Vec<Type>>) -> Self {
+    let named = Type::Named { name, generics };
+
+    if optional {
+      Type::Optional(Box::new(named))
+    } else {
+      named
     }
   }
 
+  pub fn union(types: Vec<Type>) -> Self {
+    Type::Union(types)
+  }
+
+  pub fn intersection(types: Vec<Type>) -> Self {
+    Type::Intersection(types)
+  }
+
+  pub fn literal(value: &str) -> Self {
+    Type::Literal(value.to_string())
+  }
+
+  pub fn optional(inner: Type) -> Self {
+    Type::Optional(Box::new(inner))
+  }
+
   pub fn is_optional(&self) -> bool {
-    self.optional
+    matches!(self, Type::Optional(_))
   }
 }
 
@@ -212,261 +273,896 @@ impl_ast!(
   EnumModule
 );
 
+/// Fluent front door for the node constructors above. A `StructModule` or
+/// `EndpointModule` built by hand is a pyramid of nested `Type::new(Ident::new(...), ...)`
+/// calls (see the tests below); `AstBuilder` flattens that into a chain.
+pub struct AstBuilder;
+
+impl AstBuilder {
+  pub fn new() -> Self {
+    Self
+  }
+
+  pub fn ident(&self, name: &str) -> Ident {
+    Ident::new(name)
+  }
+
+  pub fn array_of(&self, inner: Type) -> Type {
+    Type::new(self.ident("Array"), false, Some(vec![inner]))
+  }
+
+  pub fn optional(&self, ty: Type) -> Type {
+    Type::optional(ty)
+  }
+
+  pub fn field(&self, name: &str, ty: Type) -> Field {
+    Field::new(self.ident(name), ty)
+  }
+
+  pub fn method(&self, name: &str) -> MethodBuilder {
+    MethodBuilder::new(name)
+  }
+
+  pub fn struct_module(&self, name: &str) -> StructModuleBuilder {
+    StructModuleBuilder::new(name)
+  }
+
+  pub fn endpoint_module(&self, name: &str) -> EndpointModuleBuilder {
+    EndpointModuleBuilder::new(name)
+  }
+}
+
+impl Default for AstBuilder {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+pub struct MethodBuilder {
+  name: Ident,
+  parameters: Vec<Parameter>,
+  return_type: Option<Type>,
+}
+
+impl MethodBuilder {
+  fn new(name: &str) -> Self {
+    Self {
+      name: Ident::new(name),
+      parameters: Vec::new(),
+      return_type: None,
+    }
+  }
+
+  pub fn param(mut self, name: &str, ty: Type) -> Self {
+    self.parameters.push(Parameter::new(Ident::new(name), ty));
+    self
+  }
+
+  pub fn returns(mut self, ty: Type) -> Self {
+    self.return_type = Some(ty);
+    self
+  }
+
+  pub fn build(self) -> Method {
+    let return_type = self
+      .return_type
+      .expect("MethodBuilder::build called without a prior call to returns()");
+
+    Method::new(
+      self.name,
+      if self.parameters.is_empty() {
+        None
+      } else {
+        Some(self.parameters)
+      },
+      return_type,
+    )
+  }
+}
+
+pub struct StructModuleBuilder {
+  name: Ident,
+  imports: Vec<Import>,
+  fields: Vec<Field>,
+}
+
+impl StructModuleBuilder {
+  fn new(name: &str) -> Self {
+    Self {
+      name: Ident::new(name),
+      imports: Vec::new(),
+      fields: Vec::new(),
+    }
+  }
+
+  pub fn import(mut self, symbol: &str, source: &str) -> Self {
+    self.imports.push(Import::new(Ident::new(symbol), source));
+    self
+  }
+
+  pub fn field(mut self, name: &str, ty: Type) -> Self {
+    self.fields.push(Field::new(Ident::new(name), ty));
+    self
+  }
+
+  pub fn build(self) -> StructModule {
+    StructModule::new(
+      self.name.clone(),
+      if self.imports.is_empty() {
+        None
+      } else {
+        Some(self.imports)
+      },
+      Some(Struct::new(
+        self.name,
+        if self.fields.is_empty() {
+          None
+        } else {
+          Some(self.fields)
+        },
+      )),
+    )
+  }
+}
+
+pub struct EndpointModuleBuilder {
+  name: Ident,
+  imports: Vec<Import>,
+  methods: Vec<Method>,
+}
+
+impl EndpointModuleBuilder {
+  fn new(name: &str) -> Self {
+    Self {
+      name: Ident::new(name),
+      imports: Vec::new(),
+      methods: Vec::new(),
+    }
+  }
+
+  pub fn import(mut self, symbol: &str, source: &str) -> Self {
+    self.imports.push(Import::new(Ident::new(symbol), source));
+    self
+  }
+
+  pub fn method(mut self, method: Method) -> Self {
+    self.methods.push(method);
+    self
+  }
+
+  pub fn build(self) -> EndpointModule {
+    EndpointModule::new(
+      self.name,
+      if self.imports.is_empty() {
+        None
+      } else {
+        Some(self.imports)
+      },
+      if self.methods.is_empty() {
+        None
+      } else {
+        Some(self.methods)
+      },
+    )
+  }
+}
+
 enum CodegenOpts<'a> {
-  TypeWithoutOptional(bool),
   ModuleHeader(&'a str),
-  MethodEndpointName(&'a Ident),
   None,
 }
 
+/// One method per AST node family. A backend owns the target language's
+/// syntax entirely; `codegen` only walks the tree and unpacks `CodegenOpts`
+/// into the arguments each method needs.
+pub trait Backend {
+  fn emit_ident(&self, node: &Ident) -> String;
+  fn emit_import(&self, node: &Import) -> String;
+  fn emit_type(&self, node: &Type, without_optional: bool) -> String;
+  fn emit_field(&self, node: &Field) -> String;
+  fn emit_struct(&self, node: &Struct) -> String;
+  fn emit_struct_module(&self, node: &StructModule, header: Option<&str>) -> String;
+  fn emit_parameter(&self, node: &Parameter) -> String;
+  fn emit_method(&self, node: &Method, endpoint_name: &Ident) -> String;
+  fn emit_endpoint_module(&self, node: &EndpointModule, header: Option<&str>) -> String;
+  fn emit_enum_variant(&self, node: &EnumVariant) -> String;
+  fn emit_enum(&self, node: &Enum) -> String;
+  fn emit_enum_module(&self, node: &EnumModule, header: Option<&str>) -> String;
+}
+
 #[inline]
-fn codegen_imports(imports: &Option<Rc<Vec<Import>>>) -> Option<String> {
+fn codegen_imports<B: Backend>(backend: &B, imports: &Option<Rc<Vec<Import>>>) -> Option<String> {
   imports.as_ref().map(|list| {
     list
       .iter()
-      .map(|i| codegen(i, CodegenOpts::None))
+      .map(|i| backend.emit_import(i))
       .collect::<Vec<String>>()
       .join("\n")
   })
 }
 
 #[inline]
-fn codegen<T: AST>(ast: &T, opts: CodegenOpts) -> String {
+fn codegen<T: AST, B: Backend>(backend: &B, ast: &T, opts: CodegenOpts) -> String {
   match ast.kind() {
-    ASTKind::Ident(node) => (*node.0).clone(),
-    ASTKind::Import(node) => {
-      format!(
-        "import type {} from \"{}\";",
-        codegen(&node.symbol, CodegenOpts::None),
-        node.source
-      )
-    }
-    ASTKind::Type(node) => {
-      let without_optional = match opts {
-        CodegenOpts::TypeWithoutOptional(val) => val,
-        _ => false,
-      };
-
-      let name = codegen(&node.name, CodegenOpts::None);
-
-      let tail = if !without_optional && node.optional {
-        " | undefined"
-      } else {
-        ""
+    ASTKind::Ident(node) => backend.emit_ident(node),
+    ASTKind::Import(node) => backend.emit_import(node),
+    ASTKind::Type(node) => backend.emit_type(node, false),
+    ASTKind::Field(node) => backend.emit_field(node),
+    ASTKind::Struct(node) => backend.emit_struct(node),
+    ASTKind::StructModule(node) => {
+      let header = match opts {
+        CodegenOpts::ModuleHeader(val) => Some(val),
+        _ => None,
       };
 
-      let inner = node
-        .inner
-        .as_ref()
-        .map(|val| {
-          let types = val
-            .iter()
-            .map(|t| codegen(t, CodegenOpts::None))
-            .collect::<Vec<String>>()
-            .join(", ");
-
-          format!("<{}>", types)
-        })
-        .unwrap_or_default();
-
-      concat_string!(name, inner, tail)
+      backend.emit_struct_module(node, header)
     }
-    ASTKind::Field(node) => {
-      let tail = if node.r#type.is_optional() { "?" } else { "" };
-
-      format!(
-        "  {}{}: {};",
-        codegen(&node.name, CodegenOpts::None),
-        tail,
-        codegen(&node.r#type, CodegenOpts::TypeWithoutOptional(true))
-      )
+    ASTKind::Parameter(node) => backend.emit_parameter(node),
+    ASTKind::Method(_) => {
+      panic!("codegen() cannot render a Method on its own — it needs the endpoint's Ident, so call Backend::emit_method directly")
     }
-    ASTKind::Struct(node) => {
-      let fields: Option<Vec<String>> = node
-        .fields
-        .as_ref()
-        .map(|list| list.iter().map(|f| codegen(f, CodegenOpts::None)).collect());
+    ASTKind::EndpointModule(node) => {
+      let header = match opts {
+        CodegenOpts::ModuleHeader(val) => Some(val),
+        _ => None,
+      };
 
-      format!(
-        "export default interface {} {{\n{}\n}}",
-        codegen(&node.name, CodegenOpts::None),
-        fields.map(|list| list.join("\n")).unwrap_or_default()
-      )
+      backend.emit_endpoint_module(node, header)
     }
-    ASTKind::StructModule(node) => {
+    ASTKind::EnumVariant(node) => backend.emit_enum_variant(node),
+    ASTKind::Enum(node) => backend.emit_enum(node),
+    ASTKind::EnumModule(node) => {
       let header = match opts {
         CodegenOpts::ModuleHeader(val) => Some(val),
         _ => None,
+      };
+
+      backend.emit_enum_module(node, header)
+    }
+  }
+}
+
+/// `|` binds looser than `&`, so an intersection member that renders with
+/// a top-level `|` — a `Union`, or an `Optional` (always rendered with its
+/// `| undefined` tail here, since intersection members are emitted with
+/// `without_optional: false`) — needs parens to keep its grouping when
+/// joined with ` & `. A union member never needs this: its own `|`
+/// members are at the *same* precedence, and an `Intersection` member
+/// already binds tighter, so both render correctly unparenthesized.
+fn needs_parens_in_intersection(node: &Type) -> bool {
+  matches!(node, Type::Union(_) | Type::Optional(_))
+}
+
+/// Renders an `ASTKind` tree as TypeScript, the way `ts_a` has always
+/// emitted code. The only backend today, but new targets (e.g. a Kotlin
+/// backend) only need their own `Backend` implementation, not a fork of
+/// the tree walk.
+pub struct TypeScriptBackend;
+
+impl Backend for TypeScriptBackend {
+  fn emit_ident(&self, node: &Ident) -> String {
+    (*node.0).clone()
+  }
+
+  fn emit_import(&self, node: &Import) -> String {
+    format!(
+      "import type {} from \"{}\";",
+      self.emit_ident(&node.symbol),
+      node.source
+    )
+  }
+
+  fn emit_type(&self, node: &Type, without_optional: bool) -> String {
+    match node {
+      Type::Named { name, generics } => {
+        let generics = generics
+          .as_ref()
+          .map(|list| {
+            let types = list
+              .iter()
+              .map(|t| self.emit_type(t, false))
+              .collect::<Vec<String>>()
+              .join(", ");
+
+            format!("<{}>", types)
+          })
+          .unwrap_or_default();
+
+        concat_string!(self.emit_ident(name), generics)
       }
+      Type::Union(members) => members
+        .iter()
+        .map(|t| self.emit_type(t, false))
+        .collect::<Vec<String>>()
+        .join(" | "),
+      Type::Intersection(members) => members
+        .iter()
+        .map(|t| {
+          let rendered = self.emit_type(t, false);
+
+          if needs_parens_in_intersection(t) {
+            format!("({})", rendered)
+          } else {
+            rendered
+          }
+        })
+        .collect::<Vec<String>>()
+        .join(" & "),
+      Type::Literal(value) => format!("'{}'", value),
+      Type::Optional(inner) => {
+        let rendered = self.emit_type(inner, false);
+
+        if without_optional {
+          rendered
+        } else {
+          concat_string!(rendered, " | undefined")
+        }
+      }
+    }
+  }
+
+  fn emit_field(&self, node: &Field) -> String {
+    let tail = if node.r#type.is_optional() { "?" } else { "" };
+
+    format!(
+      "  {}{}: {};",
+      self.emit_ident(&node.name),
+      tail,
+      self.emit_type(&node.r#type, true)
+    )
+  }
+
+  fn emit_struct(&self, node: &Struct) -> String {
+    let fields: Option<Vec<String>> = node
+      .fields
+      .as_ref()
+      .map(|list| list.iter().map(|f| self.emit_field(f)).collect());
+
+    format!(
+      "export default interface {} {{\n{}\n}}",
+      self.emit_ident(&node.name),
+      fields.map(|list| list.join("\n")).unwrap_or_default()
+    )
+  }
+
+  fn emit_struct_module(&self, node: &StructModule, header: Option<&str>) -> String {
+    let header = header
       .map(|val| {
         let sep = "\n";
         concat_string!(val, sep)
       })
       .unwrap_or_default();
 
-      let imports = codegen_imports(&node.imports)
-        .map(|i| {
-          let sep = "\n\n";
-          concat_string!(i, sep)
-        })
-        .unwrap_or_default();
+    let imports = codegen_imports(self, &node.imports)
+      .map(|i| {
+        let sep = "\n\n";
+        concat_string!(i, sep)
+      })
+      .unwrap_or_default();
+
+    let content = node
+      .content
+      .as_ref()
+      .map(|d| self.emit_struct(d))
+      .unwrap_or_default();
 
-      let content = node
-        .content
+    concat_string!(header, imports, content)
+  }
+
+  fn emit_parameter(&self, node: &Parameter) -> String {
+    format!(
+      "{}: {}",
+      self.emit_ident(&node.name),
+      self.emit_type(&node.r#type, false)
+    )
+  }
+
+  fn emit_method(&self, node: &Method, endpoint_name: &Ident) -> String {
+    let name = self.emit_ident(&node.name);
+    let parameters: Option<Vec<String>> = node
+      .parameters
+      .as_ref()
+      .map(|parameters| parameters.iter().map(|p| self.emit_parameter(p)).collect());
+
+    let parameter_names: Option<Vec<String>> = node.parameters.as_ref().map(|parameters| {
+      parameters
+        .iter()
+        .map(|p| self.emit_ident(&p.name))
+        .collect()
+    });
+
+    format!(
+      "function _{}({}): Promise<{}> {{\n  client.call(\"{}\", \"{}\"{});\n}}",
+      name,
+      parameters
         .as_ref()
-        .map(|d| codegen(d, CodegenOpts::None))
-        .unwrap_or_default();
+        .map(|list| list.join(", "))
+        .unwrap_or_default(),
+      self.emit_type(&node.return_type, false),
+      self.emit_ident(endpoint_name),
+      name,
+      parameter_names
+        .as_ref()
+        .map(|list| format!(", {{{}}}", list.join(", ")))
+        .unwrap_or_default()
+    )
+  }
 
-      concat_string!(header, imports, content)
-    }
-    ASTKind::Parameter(node) => {
-      format!(
-        "{}: {}",
-        codegen(&node.name, CodegenOpts::None),
-        codegen(&node.r#type, CodegenOpts::None)
-      )
-    }
-    ASTKind::Method(node) => {
-      let endpoint_name = match opts {
-        CodegenOpts::MethodEndpointName(name) => name,
-        _ => panic!("No value for CodegenOpts::MethodEndpointName"),
-      };
+  fn emit_endpoint_module(&self, node: &EndpointModule, header: Option<&str>) -> String {
+    let header = header
+      .map(|val| {
+        let sep = "\n";
+        concat_string!(val, sep)
+      })
+      .unwrap_or_default();
 
-      let name = codegen(&node.name, CodegenOpts::None);
-      let parameters: Option<Vec<String>> = node.parameters.as_ref().map(|parameters| {
-        parameters
+    let imports = codegen_imports(self, &node.imports)
+      .map(|val| {
+        let sep = "\n\n";
+        concat_string!(val, sep)
+      })
+      .unwrap_or_default();
+
+    let items = node
+      .content
+      .as_ref()
+      .map(|items| {
+        let combined: String = items
           .iter()
-          .map(|p| codegen(p, CodegenOpts::None))
-          .collect()
-      });
+          .map(|i| self.emit_method(i, &node.name))
+          .collect::<Vec<String>>()
+          .join("\n\n");
+
+        let sep = "\n\n";
 
-      let parameter_names: Option<Vec<String>> = node.parameters.as_ref().map(|parameters| {
-        parameters
+        concat_string!(combined, sep)
+      })
+      .unwrap_or_default();
+
+    let exports = node
+      .content
+      .as_ref()
+      .map(|items| {
+        let combined = items
           .iter()
-          .map(|p| codegen(&p.name, CodegenOpts::None))
-          .collect()
-      });
-
-      format!(
-        "function _{}({}): Promise<{}> {{\n  client.call(\"{}\", \"{}\"{});\n}}",
-        name,
-        parameters
-          .as_ref()
-          .map(|list| list.join(", "))
-          .unwrap_or_default(),
-        codegen(&node.return_type, CodegenOpts::None),
-        codegen(endpoint_name, CodegenOpts::None),
-        name,
-        parameter_names
-          .as_ref()
-          .map(|list| format!(", {{{}}}", list.join(", ")))
-          .unwrap_or_default()
-      )
-    }
-    ASTKind::EndpointModule(node) => {
-      let header = match opts {
-        CodegenOpts::ModuleHeader(val) => Some(val),
-        _ => None,
-      }
+          .map(|i| {
+            let name = self.emit_ident(&i.name);
+            format!("  _{n} as {n},", n = name)
+          })
+          .collect::<Vec<String>>()
+          .join("\n");
+
+        format!("export {{\n{}\n}};", combined)
+      })
+      .unwrap_or_default();
+
+    concat_string!(header, imports, items, exports)
+  }
+
+  fn emit_enum_variant(&self, node: &EnumVariant) -> String {
+    let name = self.emit_ident(&node.0);
+    format!("{n} = '{n}'", n = name)
+  }
+
+  fn emit_enum(&self, node: &Enum) -> String {
+    let variants = node
+      .variants
+      .as_ref()
+      .map(|items| {
+        items
+          .iter()
+          .map(|val| format!("  {},", self.emit_enum_variant(val)))
+          .collect::<Vec<String>>()
+          .join("\n")
+      })
+      .unwrap_or_default();
+
+    format!(
+      "export default enum {} {{\n{}\n}}",
+      self.emit_ident(&node.name),
+      variants,
+    )
+  }
+
+  fn emit_enum_module(&self, node: &EnumModule, header: Option<&str>) -> String {
+    let header = header
       .map(|val| {
         let sep = "\n";
         concat_string!(val, sep)
       })
       .unwrap_or_default();
 
-      let imports = codegen_imports(&node.imports)
-        .map(|val| {
-          let sep = "\n\n";
-          concat_string!(val, sep)
-        })
-        .unwrap_or_default();
+    let content = node
+      .content
+      .as_ref()
+      .map(|val| self.emit_enum(val))
+      .unwrap_or_default();
 
-      let items = node
-        .content
-        .as_ref()
-        .map(|items| {
-          let combined: String = items
-            .iter()
-            .map(|i| codegen(i, CodegenOpts::MethodEndpointName(&node.name)))
-            .collect::<Vec<String>>()
-            .join("\n\n");
+    let sep = "\n";
+    concat_string!(header, sep, content)
+  }
+}
 
-          let sep = "\n\n";
+/// Mutates an `ASTKind` tree in place. Every method defaults to recursing
+/// into the node's children, so a transformation pass only needs to
+/// override the node kinds it actually rewrites.
+pub trait VisitMut {
+  fn visit_ident(&mut self, _node: &mut Ident) {}
 
-          concat_string!(combined, sep)
-        })
-        .unwrap_or_default();
+  fn visit_import(&mut self, node: &mut Import) {
+    self.visit_ident(&mut node.symbol);
+  }
 
-      let exports = node
-        .content
-        .as_ref()
-        .map(|items| {
-          let combined = items
-            .iter()
-            .map(|i| {
-              let name = codegen(&i.name, CodegenOpts::None);
-              format!("  _{n} as {n},", n = name)
-            })
-            .collect::<Vec<String>>()
-            .join("\n");
-
-          format!("export {{\n{}\n}};", combined)
-        })
-        .unwrap_or_default();
+  fn visit_type(&mut self, node: &mut Type) {
+    walk_type_mut(self, node);
+  }
+
+  fn visit_field(&mut self, node: &mut Field) {
+    walk_field_mut(self, node);
+  }
+
+  fn visit_struct(&mut self, node: &mut Struct) {
+    walk_struct_mut(self, node);
+  }
 
-      concat_string!(header, imports, items, exports)
+  fn visit_struct_module(&mut self, node: &mut StructModule) {
+    walk_struct_module_mut(self, node);
+  }
+
+  fn visit_parameter(&mut self, node: &mut Parameter) {
+    walk_parameter_mut(self, node);
+  }
+
+  fn visit_method(&mut self, node: &mut Method) {
+    walk_method_mut(self, node);
+  }
+
+  fn visit_endpoint_module(&mut self, node: &mut EndpointModule) {
+    walk_endpoint_module_mut(self, node);
+  }
+
+  fn visit_enum_variant(&mut self, node: &mut EnumVariant) {
+    self.visit_ident(&mut node.0);
+  }
+
+  fn visit_enum(&mut self, node: &mut Enum) {
+    walk_enum_mut(self, node);
+  }
+
+  fn visit_enum_module(&mut self, node: &mut EnumModule) {
+    walk_enum_module_mut(self, node);
+  }
+}
+
+fn walk_type_mut<V: VisitMut + ?Sized>(visitor: &mut V, node: &mut Type) {
+  match node {
+    Type::Named { name, generics } => {
+      visitor.visit_ident(name);
+
+      if let Some(generics) = generics {
+        for t in generics.iter_mut() {
+          visitor.visit_type(t);
+        }
+      }
     }
-    ASTKind::EnumVariant(node) => {
-      let name = codegen(&node.0, CodegenOpts::None);
-      format!("{n} = '{n}'", n = name)
+    Type::Union(members) | Type::Intersection(members) => {
+      for t in members.iter_mut() {
+        visitor.visit_type(t);
+      }
     }
-    ASTKind::Enum(node) => {
-      let variants = node
-        .variants
-        .as_ref()
-        .map(|items| {
-          items
-            .iter()
-            .map(|val| format!("  {},", codegen(val, CodegenOpts::None)))
-            .collect::<Vec<String>>()
-            .join("\n")
-        })
-        .unwrap_or_default();
+    Type::Literal(_) => {}
+    Type::Optional(inner) => visitor.visit_type(inner),
+  }
+}
 
-      format!(
-        "export default enum {} {{\n{}\n}}",
-        codegen(&node.name, CodegenOpts::None),
-        variants,
-      )
+fn walk_field_mut<V: VisitMut + ?Sized>(visitor: &mut V, node: &mut Field) {
+  visitor.visit_ident(&mut node.name);
+  visitor.visit_type(&mut node.r#type);
+}
+
+fn walk_struct_mut<V: VisitMut + ?Sized>(visitor: &mut V, node: &mut Struct) {
+  visitor.visit_ident(&mut node.name);
+
+  if let Some(fields) = node.fields.as_mut() {
+    for f in Rc::make_mut(fields).iter_mut() {
+      visitor.visit_field(f);
     }
-    ASTKind::EnumModule(node) => {
-      let header = match opts {
-        CodegenOpts::ModuleHeader(val) => Some(val),
-        _ => None,
+  }
+}
+
+fn walk_struct_module_mut<V: VisitMut + ?Sized>(visitor: &mut V, node: &mut StructModule) {
+  visitor.visit_ident(&mut node.name);
+
+  if let Some(imports) = node.imports.as_mut() {
+    for i in Rc::make_mut(imports).iter_mut() {
+      visitor.visit_import(i);
+    }
+  }
+
+  if let Some(content) = node.content.as_mut() {
+    visitor.visit_struct(content);
+  }
+}
+
+fn walk_parameter_mut<V: VisitMut + ?Sized>(visitor: &mut V, node: &mut Parameter) {
+  visitor.visit_ident(&mut node.name);
+  visitor.visit_type(&mut node.r#type);
+}
+
+fn walk_method_mut<V: VisitMut + ?Sized>(visitor: &mut V, node: &mut Method) {
+  visitor.visit_ident(&mut node.name);
+
+  if let Some(parameters) = node.parameters.as_mut() {
+    for p in Rc::make_mut(parameters).iter_mut() {
+      visitor.visit_parameter(p);
+    }
+  }
+
+  visitor.visit_type(&mut node.return_type);
+}
+
+fn walk_endpoint_module_mut<V: VisitMut + ?Sized>(visitor: &mut V, node: &mut EndpointModule) {
+  visitor.visit_ident(&mut node.name);
+
+  if let Some(imports) = node.imports.as_mut() {
+    for i in Rc::make_mut(imports).iter_mut() {
+      visitor.visit_import(i);
+    }
+  }
+
+  if let Some(content) = node.content.as_mut() {
+    for m in Rc::make_mut(content).iter_mut() {
+      visitor.visit_method(m);
+    }
+  }
+}
+
+fn walk_enum_mut<V: VisitMut + ?Sized>(visitor: &mut V, node: &mut Enum) {
+  visitor.visit_ident(&mut node.name);
+
+  if let Some(variants) = node.variants.as_mut() {
+    for v in Rc::make_mut(variants).iter_mut() {
+      visitor.visit_enum_variant(v);
+    }
+  }
+}
+
+fn walk_enum_module_mut<V: VisitMut + ?Sized>(visitor: &mut V, node: &mut EnumModule) {
+  visitor.visit_ident(&mut node.name);
+
+  if let Some(content) = node.content.as_mut() {
+    visitor.visit_enum(content);
+  }
+}
+
+/// Runs `passes` over a `StructModule`, in order, before it reaches
+/// `codegen`.
+pub fn run_struct_module_passes(node: &mut StructModule, passes: &mut [&mut dyn VisitMut]) {
+  for pass in passes.iter_mut() {
+    pass.visit_struct_module(node);
+  }
+}
+
+/// Runs `passes` over an `EndpointModule`, in order, before it reaches
+/// `codegen`.
+pub fn run_endpoint_module_passes(node: &mut EndpointModule, passes: &mut [&mut dyn VisitMut]) {
+  for pass in passes.iter_mut() {
+    pass.visit_endpoint_module(node);
+  }
+}
+
+/// Runs `passes` over an `EnumModule`, in order, before it reaches
+/// `codegen`.
+pub fn run_enum_module_passes(node: &mut EnumModule, passes: &mut [&mut dyn VisitMut]) {
+  for pass in passes.iter_mut() {
+    pass.visit_enum_module(node);
+  }
+}
+
+/// Read-only counterpart of `VisitMut`, covering only the node kinds a
+/// pass needs to inspect a module's declarations (as opposed to its
+/// imports) — e.g. to find out which imported symbols are actually used.
+pub trait Visit {
+  fn visit_ident(&mut self, _node: &Ident) {}
+
+  fn visit_type(&mut self, node: &Type) {
+    walk_type(self, node);
+  }
+
+  fn visit_field(&mut self, node: &Field) {
+    walk_field(self, node);
+  }
+
+  fn visit_parameter(&mut self, node: &Parameter) {
+    walk_parameter(self, node);
+  }
+}
+
+fn walk_type<V: Visit + ?Sized>(visitor: &mut V, node: &Type) {
+  match node {
+    Type::Named { name, generics } => {
+      visitor.visit_ident(name);
+
+      if let Some(generics) = generics {
+        for t in generics.iter() {
+          visitor.visit_type(t);
+        }
       }
-      .map(|val| {
-        let sep = "\n";
-        concat_string!(val, sep)
-      })
-      .unwrap_or_default();
+    }
+    Type::Union(members) | Type::Intersection(members) => {
+      for t in members.iter() {
+        visitor.visit_type(t);
+      }
+    }
+    Type::Literal(_) => {}
+    Type::Optional(inner) => visitor.visit_type(inner),
+  }
+}
 
-      let content = node
-        .content
-        .as_ref()
-        .map(|val| codegen(val, CodegenOpts::None))
-        .unwrap_or_default();
+fn walk_field<V: Visit + ?Sized>(visitor: &mut V, node: &Field) {
+  visitor.visit_type(&node.r#type);
+}
+
+fn walk_parameter<V: Visit + ?Sized>(visitor: &mut V, node: &Parameter) {
+  visitor.visit_type(&node.r#type);
+}
+
+#[derive(Default)]
+struct ReferencedIdents(HashSet<String>);
+
+impl Visit for ReferencedIdents {
+  fn visit_ident(&mut self, node: &Ident) {
+    self.0.insert((*node.0).clone());
+  }
+}
+
+fn referenced_idents_in_struct(node: &Struct) -> HashSet<String> {
+  let mut collector = ReferencedIdents::default();
+
+  if let Some(fields) = node.fields.as_ref() {
+    for f in fields.iter() {
+      collector.visit_field(f);
+    }
+  }
+
+  collector.0
+}
+
+fn referenced_idents_in_methods(methods: &[Method]) -> HashSet<String> {
+  let mut collector = ReferencedIdents::default();
+
+  for m in methods.iter() {
+    if let Some(parameters) = m.parameters.as_ref() {
+      for p in parameters.iter() {
+        collector.visit_parameter(p);
+      }
+    }
+
+    collector.visit_type(&m.return_type);
+  }
+
+  collector.0
+}
+
+fn retain_referenced_imports(imports: &mut Option<Rc<Vec<Import>>>, referenced: &HashSet<String>) {
+  let list = match imports.as_ref() {
+    Some(list) => list,
+    None => return,
+  };
+
+  let kept: Vec<Import> = list
+    .iter()
+    .filter(|i| referenced.contains(&*i.symbol.0))
+    .cloned()
+    .collect();
+
+  *imports = Some(Rc::new(kept));
+}
+
+fn dedup_imports(imports: &mut Option<Rc<Vec<Import>>>) {
+  let list = match imports.as_ref() {
+    Some(list) => list,
+    None => return,
+  };
+
+  let mut seen: Vec<(Rc<String>, String)> = Vec::new();
+  let deduped: Vec<Import> = list
+    .iter()
+    .filter(|i| {
+      let key = (i.source.clone(), (*i.symbol.0).clone());
+
+      if seen.contains(&key) {
+        false
+      } else {
+        seen.push(key);
+        true
+      }
+    })
+    .cloned()
+    .collect();
+
+  *imports = Some(Rc::new(deduped));
+}
+
+fn rename_colliding_import(module_name: &Ident, imports: &mut Option<Rc<Vec<Import>>>) {
+  let list = match imports.as_mut() {
+    Some(list) => list,
+    None => return,
+  };
 
-      let sep = "\n";
-      concat_string!(header, sep, content)
+  for import in Rc::make_mut(list).iter_mut() {
+    if *import.symbol.0 == *module_name.0 {
+      import.symbol = Ident::new(&concat_string!(&*import.symbol.0, "$1"));
     }
   }
 }
 
+/// Merges imports that share a source, keeping only the first occurrence
+/// of a given symbol from that source — the same coalescing a hand-written
+/// client would do to avoid two `import type` statements for one file.
+pub struct MergeDuplicateImportsPass;
+
+impl VisitMut for MergeDuplicateImportsPass {
+  fn visit_struct_module(&mut self, node: &mut StructModule) {
+    dedup_imports(&mut node.imports);
+  }
+
+  fn visit_endpoint_module(&mut self, node: &mut EndpointModule) {
+    dedup_imports(&mut node.imports);
+  }
+}
+
+/// Drops imports whose symbol never appears in the module's declarations,
+/// so a struct/endpoint file doesn't ship an unused-import lint warning.
+pub struct DropUnreferencedImportsPass;
+
+impl VisitMut for DropUnreferencedImportsPass {
+  fn visit_struct_module(&mut self, node: &mut StructModule) {
+    let referenced = node
+      .content
+      .as_ref()
+      .map(referenced_idents_in_struct)
+      .unwrap_or_default();
+
+    retain_referenced_imports(&mut node.imports, &referenced);
+  }
+
+  fn visit_endpoint_module(&mut self, node: &mut EndpointModule) {
+    let referenced = node
+      .content
+      .as_ref()
+      .map(|methods| referenced_idents_in_methods(methods))
+      .unwrap_or_default();
+
+    retain_referenced_imports(&mut node.imports, &referenced);
+  }
+}
+
+/// Renames an imported symbol that collides with the module's own default
+/// export, appending a `$1` suffix so the generated file still compiles
+/// (e.g. a `Foo` model imported into a module that itself default-exports
+/// `interface Foo`).
+pub struct RenameCollidingIdentsPass;
+
+impl VisitMut for RenameCollidingIdentsPass {
+  fn visit_struct_module(&mut self, node: &mut StructModule) {
+    rename_colliding_import(&node.name, &mut node.imports);
+  }
+
+  fn visit_endpoint_module(&mut self, node: &mut EndpointModule) {
+    rename_colliding_import(&node.name, &mut node.imports);
+  }
+}
+
 #[cfg(test)]
 mod tests {
+  use std::rc::Rc;
+
   use crate::ts_a::{
-    codegen, CodegenOpts, EndpointModule, Enum, EnumModule, EnumVariant, Field, Ident, Import,
-    Method, Parameter, Struct, StructModule, Type,
+    codegen, run_endpoint_module_passes, run_struct_module_passes, AstBuilder, CodegenOpts,
+    DropUnreferencedImportsPass, EndpointModule, Enum, EnumModule, EnumVariant, Field, Ident,
+    Import, MergeDuplicateImportsPass, Method, Parameter, RenameCollidingIdentsPass, Struct,
+    StructModule, Type, TypeScriptBackend,
   };
 
   #[test]
@@ -494,6 +1190,7 @@ mod tests {
     );
 
     let code = codegen(
+      &TypeScriptBackend,
       &struct_mod,
       CodegenOpts::ModuleHeader("/**\n * Some header\n */"),
     );
@@ -567,6 +1264,7 @@ export default interface ChartSeries {
     );
 
     let code = codegen(
+      &TypeScriptBackend,
       &endpoint_mod,
       CodegenOpts::ModuleHeader("/**\n * Some header\n */"),
     );
@@ -610,6 +1308,7 @@ export {
     );
 
     let code = codegen(
+      &TypeScriptBackend,
       &enum_mod,
       CodegenOpts::ModuleHeader("/**\n * Some header\n */"),
     );
@@ -627,4 +1326,204 @@ export default enum MyEnum {
 }"
     );
   }
+
+  #[test]
+  fn should_merge_duplicate_imports_before_codegen() {
+    let mut endpoint_mod = EndpointModule::new(
+      Ident::new("DashboardEndpoint"),
+      Some(vec![
+        Import::new(Ident::new("A"), "./models"),
+        Import::new(Ident::new("A"), "./models"),
+      ]),
+      None,
+    );
+
+    run_endpoint_module_passes(&mut endpoint_mod, &mut [&mut MergeDuplicateImportsPass]);
+
+    let code = codegen(&TypeScriptBackend, &endpoint_mod, CodegenOpts::None);
+    assert_eq!(code, "import type A from \"./models\";\n\n")
+  }
+
+  #[test]
+  fn should_drop_unreferenced_imports_before_codegen() {
+    let mut struct_mod = StructModule::new(
+      Ident::new("ChartSeries"),
+      Some(vec![
+        Import::new(Ident::new("string"), "./unused"),
+        Import::new(Ident::new("Array"), "./used"),
+      ]),
+      Some(Struct::new(
+        Ident::new("ChartSeries"),
+        Some(vec![Field::new(
+          Ident::new("data"),
+          Type::new(Ident::new("Array"), false, None),
+        )]),
+      )),
+    );
+
+    run_struct_module_passes(&mut struct_mod, &mut [&mut DropUnreferencedImportsPass]);
+
+    let code = codegen(&TypeScriptBackend, &struct_mod, CodegenOpts::None);
+    assert_eq!(
+      code,
+      "import type Array from \"./used\";\n\nexport default interface ChartSeries {\n  data: Array;\n}"
+    )
+  }
+
+  #[test]
+  fn should_rename_an_import_colliding_with_the_module_name() {
+    let mut struct_mod = StructModule::new(
+      Ident::new("ChartSeries"),
+      Some(vec![Import::new(Ident::new("ChartSeries"), "./other")]),
+      None,
+    );
+
+    run_struct_module_passes(&mut struct_mod, &mut [&mut RenameCollidingIdentsPass]);
+
+    let code = codegen(&TypeScriptBackend, &struct_mod, CodegenOpts::None);
+    assert_eq!(code, "import type ChartSeries$1 from \"./other\";\n\n")
+  }
+
+  #[test]
+  fn should_intern_identical_ident_and_import_source_strings() {
+    let a = Ident::new("DashboardEndpoint");
+    let b = Ident::new("DashboardEndpoint");
+    assert!(Rc::ptr_eq(&a.0, &b.0));
+
+    let import_a = Import::new(Ident::new("A"), "./models");
+    let import_b = Import::new(Ident::new("A"), "./models");
+    assert!(Rc::ptr_eq(&import_a.source, &import_b.source));
+  }
+
+  #[test]
+  fn should_build_a_struct_module_fluently() {
+    let builder = AstBuilder::new();
+
+    let number = builder.ident("number");
+    let array_of_numbers = builder.array_of(Type::new(number, false, None));
+
+    let struct_mod = builder
+      .struct_module("ChartSeries")
+      .field("data", builder.optional(array_of_numbers))
+      .field(
+        "name",
+        Type::new(builder.ident("string"), false, None),
+      )
+      .build();
+
+    let code = codegen(&TypeScriptBackend, &struct_mod, CodegenOpts::None);
+    assert_eq!(
+      code,
+      "export default interface ChartSeries {\n  data?: Array<number>;\n  name: string;\n}"
+    )
+  }
+
+  #[test]
+  fn should_build_an_endpoint_module_fluently() {
+    let builder = AstBuilder::new();
+
+    let endpoint_mod = builder
+      .endpoint_module("DashboardEndpoint")
+      .import("HealthGridItem", "./models/HealthGridItem")
+      .method(
+        builder
+          .method("healthGridItems")
+          .returns(builder.array_of(Type::new(
+            builder.ident("HealthGridItem"),
+            false,
+            None,
+          )))
+          .build(),
+      )
+      .build();
+
+    let code = codegen(&TypeScriptBackend, &endpoint_mod, CodegenOpts::None);
+    assert_eq!(
+      code,
+      "import type HealthGridItem from \"./models/HealthGridItem\";\n\nfunction _healthGridItems(): Promise<Array<HealthGridItem>> {\n  client.call(\"DashboardEndpoint\", \"healthGridItems\");\n}\n\nexport {\n  _healthGridItems as healthGridItems,\n};"
+    )
+  }
+
+  #[test]
+  fn should_generate_code_for_a_union_of_literal_types() {
+    let field = Field::new(
+      Ident::new("status"),
+      Type::union(vec![Type::literal("active"), Type::literal("inactive")]),
+    );
+
+    assert_eq!(
+      codegen(&TypeScriptBackend, &field, CodegenOpts::None),
+      "  status: 'active' | 'inactive';"
+    )
+  }
+
+  #[test]
+  fn should_generate_code_for_an_intersection_type() {
+    let field = Field::new(
+      Ident::new("user"),
+      Type::intersection(vec![
+        Type::new(Ident::new("HasId"), false, None),
+        Type::new(Ident::new("HasName"), false, None),
+      ]),
+    );
+
+    assert_eq!(
+      codegen(&TypeScriptBackend, &field, CodegenOpts::None),
+      "  user: HasId & HasName;"
+    )
+  }
+
+  #[test]
+  fn should_parenthesize_a_union_member_of_an_intersection() {
+    let field = Field::new(
+      Ident::new("entry"),
+      Type::intersection(vec![
+        Type::union(vec![
+          Type::new(Ident::new("A"), false, None),
+          Type::new(Ident::new("B"), false, None),
+        ]),
+        Type::new(Ident::new("C"), false, None),
+      ]),
+    );
+
+    assert_eq!(
+      codegen(&TypeScriptBackend, &field, CodegenOpts::None),
+      "  entry: (A | B) & C;"
+    )
+  }
+
+  #[test]
+  fn should_not_parenthesize_an_intersection_member_of_a_union() {
+    let field = Field::new(
+      Ident::new("entry"),
+      Type::union(vec![
+        Type::intersection(vec![
+          Type::new(Ident::new("A"), false, None),
+          Type::new(Ident::new("B"), false, None),
+        ]),
+        Type::new(Ident::new("C"), false, None),
+      ]),
+    );
+
+    assert_eq!(
+      codegen(&TypeScriptBackend, &field, CodegenOpts::None),
+      "  entry: A & B | C;"
+    )
+  }
+
+  #[test]
+  fn should_keep_the_question_mark_and_drop_the_undefined_tail_on_an_optional_field() {
+    let field = Field::new(
+      Ident::new("status"),
+      Type::optional(Type::union(vec![
+        Type::literal("active"),
+        Type::literal("inactive"),
+      ])),
+    );
+
+    assert_eq!(
+      codegen(&TypeScriptBackend, &field, CodegenOpts::None),
+      "  status?: 'active' | 'inactive';"
+    )
+  }
 }
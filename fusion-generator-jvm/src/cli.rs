@@ -9,4 +9,8 @@ pub struct CLI {
   output: String,
   #[clap(short, long, default_value = "deps")]
   deps: String,
+  /// Selects the `Backend` that `ts_a::codegen` renders the AST with, e.g.
+  /// "typescript".
+  #[clap(short, long, default_value = "typescript")]
+  target: String,
 }
@@ -13,7 +13,6 @@ mod constant;
 mod error;
 mod resolver;
 mod translator;
-mod utils;
 
 fn main() -> Result<(), Box<dyn Error>> {
   Ok(())
@@ -1,59 +1,151 @@
-use std::ffi::{OsStr, OsString};
+use std::collections::HashMap;
 use std::fs::File;
-use std::io;
-use std::io::{BufReader, Read};
-use std::rc::Rc;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
 
 use coffer::{Class, ReadWrite};
-use concat_string::concat_string;
-use itertools::Itertools;
 use walkdir::WalkDir;
-use zip::read::ZipFile;
-use zip::result::ZipError;
 use zip::ZipArchive;
 
 use crate::error::ResolutionError;
-use crate::utils::ResultIterator;
+
+/// Where a resolved class's bytecode actually lives, so `Resolver` only
+/// has to open the one file/archive entry it already knows about instead
+/// of re-walking the classpath on every `resolve` call.
+enum Location {
+  File(PathBuf),
+  JarEntry { jar: PathBuf, name: String },
+}
+
+/// A classpath entry -> `Location` map, built once up front. Directory
+/// classes take precedence over jar entries of the same name, matching
+/// the classpath order a JVM itself would use.
+struct ClassIndex(HashMap<String, Location>);
+
+impl ClassIndex {
+  fn build(paths: &[String]) -> Result<Self, ResolutionError> {
+    let mut index = HashMap::new();
+
+    // Jars are indexed first, then directories, so a directory class of
+    // the same name overwrites the jar entry and wins, as `resolve` used
+    // to guarantee by always checking directories first. Within each
+    // category `entry` is used instead of `insert` so the first path in
+    // `paths` wins a same-category collision, matching the old
+    // `.next()`-based classpath-order lookup.
+    for path in paths.iter().filter(|path| path.ends_with(".jar")) {
+      let file = File::open(path)?;
+      let mut archive = ZipArchive::new(BufReader::new(file))?;
+
+      for i in 0..archive.len() {
+        let entry = archive.by_index(i)?;
+
+        if let Some(name) = entry.name().strip_suffix(".class") {
+          index.entry(name.to_string()).or_insert(Location::JarEntry {
+            jar: PathBuf::from(path),
+            name: entry.name().to_string(),
+          });
+        }
+      }
+    }
+
+    for path in paths.iter().filter(|path| !path.ends_with(".jar")) {
+      for entry in WalkDir::new(path).into_iter().filter_map(|entry| entry.ok()) {
+        if entry.path().extension().and_then(|ext| ext.to_str()) != Some("class") {
+          continue;
+        }
+
+        if let Some(name) = class_name(Path::new(path), entry.path()) {
+          match index.get(&name) {
+            Some(Location::File(_)) => {}
+            _ => {
+              index.insert(name, Location::File(entry.path().to_path_buf()));
+            }
+          }
+        }
+      }
+    }
+
+    Ok(Self(index))
+  }
+
+  fn get(&self, dep: &str) -> Option<&Location> {
+    self.0.get(dep)
+  }
+}
+
+/// Turns `root/com/example/Foo.class` into `com/example/Foo`, the same
+/// slash-separated binary name `dep` is already passed in as.
+fn class_name(root: &Path, file: &Path) -> Option<String> {
+  let relative = file.strip_prefix(root).ok()?.with_extension("");
+
+  relative
+    .components()
+    .map(|c| c.as_os_str().to_str())
+    .collect::<Option<Vec<&str>>>()
+    .map(|parts| parts.join("/"))
+}
 
 pub struct Resolver {
-  paths: Rc<Vec<String>>,
+  index: ClassIndex,
 }
 
 impl Resolver {
+  pub fn new(paths: Vec<String>) -> Result<Self, ResolutionError> {
+    Ok(Self {
+      index: ClassIndex::build(&paths)?,
+    })
+  }
+
   fn resolve(&self, dep: &str) -> Result<Class, ResolutionError> {
-    let dep = {
-      let tail = ".class";
-      concat_string!(dep, tail)
-    };
-
-    self
-      .paths
-      .iter()
-      .filter(|path| !path.ends_with(".jar"))
-      .flat_map(|path| WalkDir::new(path).into_iter())
-      .filter_map(|entry| entry.ok())
-      .filter_map(|entry| entry.path().to_str().map(|s| s.to_owned()))
-      .filter(|path| path.contains(&dep))
-      .map(|path| File::open(path).map_err(ResolutionError::from))
-      .flat_map_res(|mut file| Ok(Class::read_from(&mut file)?))
-      .next()
-      .or(
-        self
-          .paths
-          .iter()
-          .filter(|path| path.ends_with(".jar"))
-          .map(|path| File::open(path).map_err(ResolutionError::from))
-          .map_ok(BufReader::new)
-          .flat_map_res(|mut reader| Ok(ZipArchive::new(reader)?))
-          .flat_map_res(|mut archive| {
-            Ok(match archive.by_name(&dep).ok() {
-              Some(mut file) => Some(Class::read_from(&mut file)?),
-              None => None,
-            })
-          })
-          .filter_map_ok(|class| class)
-          .next(),
-      )
-      .ok_or(ResolutionError::DependencyNotResolved(dep.to_string()))?
+    match self
+      .index
+      .get(dep)
+      .ok_or_else(|| ResolutionError::DependencyNotResolved(dep.to_string()))?
+    {
+      Location::File(path) => {
+        let mut file = File::open(path)?;
+        Ok(Class::read_from(&mut file)?)
+      }
+      Location::JarEntry { jar, name } => {
+        let file = File::open(jar)?;
+        let mut archive = ZipArchive::new(BufReader::new(file))?;
+        let mut entry = archive.by_name(name)?;
+        Ok(Class::read_from(&mut entry)?)
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::fs;
+
+  use super::*;
+
+  fn write_class(dir: &Path, name: &str) {
+    fs::create_dir_all(dir).unwrap();
+    fs::write(dir.join(format!("{}.class", name)), []).unwrap();
+  }
+
+  #[test]
+  fn should_let_the_first_directory_win_a_same_category_collision() {
+    let root = std::env::temp_dir().join(format!("fusion-generator-jvm-resolver-test-{}", std::process::id()));
+    let first = root.join("first");
+    let second = root.join("second");
+    write_class(&first, "Foo");
+    write_class(&second, "Foo");
+
+    let index = ClassIndex::build(&[
+      first.to_str().unwrap().to_string(),
+      second.to_str().unwrap().to_string(),
+    ])
+    .unwrap();
+
+    match index.get("Foo") {
+      Some(Location::File(path)) => assert_eq!(path, &first.join("Foo.class")),
+      other => panic!("expected a file location from the first directory, got {:?}", other.is_some()),
+    }
+
+    fs::remove_dir_all(&root).unwrap();
   }
 }
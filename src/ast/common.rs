@@ -1,11 +1,25 @@
+use std::collections::HashSet;
 use std::rc::Rc;
 
 use concat_string::concat_string;
+use serde_json::{json, Value};
 
 use crate::ast::AST;
-use crate::ts::TSConvertable;
+use crate::error::GenError;
+use crate::ir::IrSerializable;
+use crate::ts::{TSConvertable, TryTSConvertable};
+use crate::utils::ResultIterator;
 
-#[derive(Clone)]
+// https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Lexical_grammar#keywords
+const RESERVED_WORDS: &[&str] = &[
+  "break", "case", "catch", "class", "const", "continue", "debugger", "default", "delete", "do",
+  "else", "enum", "export", "extends", "false", "finally", "for", "function", "if", "import",
+  "in", "instanceof", "new", "null", "return", "super", "switch", "this", "throw", "true", "try",
+  "typeof", "var", "void", "while", "with", "as", "implements", "interface", "let", "package",
+  "private", "protected", "public", "static", "yield",
+];
+
+#[derive(Clone, PartialEq, Eq, Hash)]
 pub struct Ident {
   name: Rc<String>,
 }
@@ -16,6 +30,16 @@ impl Ident {
       name: Rc::new(String::from(name)),
     }
   }
+
+  fn is_valid(name: &str) -> bool {
+    let mut chars = name.chars();
+
+    let starts_right = matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_' || c == '$');
+
+    starts_right
+      && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+      && !RESERVED_WORDS.contains(&name)
+  }
 }
 
 impl AST for Ident {}
@@ -26,17 +50,113 @@ impl TSConvertable for Ident {
   }
 }
 
+impl TryTSConvertable for Ident {
+  fn try_as_ts(&self) -> Result<String, GenError> {
+    let name = self.as_ts();
+
+    if !Self::is_valid(&name) {
+      return Err(GenError::InvalidIdent {
+        path: name.clone(),
+        name,
+      });
+    }
+
+    Ok(name)
+  }
+}
+
+impl IrSerializable for Ident {
+  fn as_ir(&self) -> Value {
+    json!(*self.name)
+  }
+}
+
+#[derive(Clone, PartialEq, Eq)]
+enum ImportKind {
+  Default(Ident),
+  Named(Vec<(Ident, Option<Ident>)>),
+  Namespace(Ident),
+}
+
 #[derive(Clone)]
 pub struct Import {
   source: Rc<String>,
-  symbol: Ident,
+  kind: ImportKind,
+  value: bool,
 }
 
 impl Import {
+  /// `import type {symbol} from "{source}";` — the only shape this crate
+  /// could express before named/namespace/value imports were added.
   pub fn new(symbol: Ident, source: &str) -> Self {
+    Self::default_type(symbol, source)
+  }
+
+  pub fn default_type(symbol: Ident, source: &str) -> Self {
     Self {
       source: Rc::new(source.to_string()),
-      symbol,
+      kind: ImportKind::Default(symbol),
+      value: false,
+    }
+  }
+
+  /// `import type { A, B as C } from "{source}";`
+  pub fn named(symbols: Vec<(Ident, Option<Ident>)>, source: &str) -> Self {
+    Self {
+      source: Rc::new(source.to_string()),
+      kind: ImportKind::Named(symbols),
+      value: false,
+    }
+  }
+
+  /// `import type * as {symbol} from "{source}";`
+  pub fn namespace(symbol: Ident, source: &str) -> Self {
+    Self {
+      source: Rc::new(source.to_string()),
+      kind: ImportKind::Namespace(symbol),
+      value: false,
+    }
+  }
+
+  /// Turns a `import type ...` into a value `import ...`, e.g. for the
+  /// runtime `client` helper an endpoint module calls into.
+  pub fn into_value(mut self) -> Self {
+    self.value = true;
+    self
+  }
+
+  pub(crate) fn can_coalesce_with(&self, other: &Import) -> bool {
+    matches!(
+      (&self.kind, &other.kind),
+      (ImportKind::Named(_), ImportKind::Named(_))
+    ) && self.source == other.source
+      && self.value == other.value
+  }
+
+  pub(crate) fn coalesce(&mut self, other: &Import) {
+    if let (ImportKind::Named(symbols), ImportKind::Named(other_symbols)) =
+      (&mut self.kind, &other.kind)
+    {
+      symbols.extend(other_symbols.iter().cloned());
+    }
+  }
+
+  fn keyword(&self) -> &'static str {
+    if self.value {
+      "import"
+    } else {
+      "import type"
+    }
+  }
+
+  fn path_hint(&self) -> String {
+    match &self.kind {
+      ImportKind::Default(symbol) => symbol.as_ts(),
+      ImportKind::Named(symbols) => symbols
+        .first()
+        .map(|(name, _)| name.as_ts())
+        .unwrap_or_default(),
+      ImportKind::Namespace(symbol) => symbol.as_ts(),
     }
   }
 }
@@ -45,27 +165,176 @@ impl AST for Import {}
 
 impl TSConvertable for Import {
   fn as_ts(&self) -> String {
-    format!(
-      "import type {} from \"{}\";",
-      self.symbol.as_ts(),
-      self.source
-    )
+    let clause = match &self.kind {
+      ImportKind::Default(symbol) => symbol.as_ts(),
+      ImportKind::Named(symbols) => {
+        let members: Vec<String> = symbols
+          .iter()
+          .map(|(name, alias)| match alias {
+            Some(alias) => format!("{} as {}", name.as_ts(), alias.as_ts()),
+            None => name.as_ts(),
+          })
+          .collect();
+
+        format!("{{ {} }}", members.join(", "))
+      }
+      ImportKind::Namespace(symbol) => format!("* as {}", symbol.as_ts()),
+    };
+
+    format!("{} {} from \"{}\";", self.keyword(), clause, self.source)
   }
 }
 
+impl TryTSConvertable for Import {
+  fn try_as_ts(&self) -> Result<String, GenError> {
+    if self.source.is_empty() {
+      return Err(GenError::EmptyImportSource {
+        path: self.path_hint(),
+      });
+    }
+
+    let clause = match &self.kind {
+      ImportKind::Default(symbol) => symbol.try_as_ts()?,
+      ImportKind::Named(symbols) => {
+        let members = symbols
+          .iter()
+          .map(Ok)
+          .flat_map_res(|(name, alias): &(Ident, Option<Ident>)| {
+            let name = name.try_as_ts()?;
+            let alias = alias.as_ref().map(|a| a.try_as_ts()).transpose()?;
+
+            Ok(match alias {
+              Some(alias) => format!("{} as {}", name, alias),
+              None => name,
+            })
+          })
+          .collect::<Result<Vec<String>, GenError>>()?;
+
+        format!("{{ {} }}", members.join(", "))
+      }
+      ImportKind::Namespace(symbol) => format!("* as {}", symbol.try_as_ts()?),
+    };
+
+    Ok(format!("{} {} from \"{}\";", self.keyword(), clause, self.source))
+  }
+}
+
+impl IrSerializable for Import {
+  fn as_ir(&self) -> Value {
+    let (shape, symbol, members) = match &self.kind {
+      ImportKind::Default(symbol) => ("default", Some(symbol.as_ir()), None),
+      ImportKind::Named(symbols) => (
+        "named",
+        None,
+        Some(
+          symbols
+            .iter()
+            .map(|(name, alias)| {
+              json!({
+                "name": name.as_ir(),
+                "alias": alias.as_ref().map(|a| a.as_ir()),
+              })
+            })
+            .collect::<Vec<Value>>(),
+        ),
+      ),
+      ImportKind::Namespace(symbol) => ("namespace", Some(symbol.as_ir()), None),
+    };
+
+    json!({
+      "kind": "import",
+      "importKind": shape,
+      "symbol": symbol,
+      "members": members,
+      "source": *self.source,
+      "value": self.value,
+    })
+  }
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+enum TypeShape {
+  Named(Ident, Option<Rc<Vec<Type>>>),
+  Union(Rc<Vec<Type>>),
+  Intersection(Rc<Vec<Type>>),
+}
+
 #[derive(Clone)]
 pub struct Type {
   optional: bool,
-  inner: Option<Rc<Vec<Type>>>,
-  name: Ident,
+  shape: TypeShape,
 }
 
 impl Type {
   pub fn new(name: Ident, optional: bool, inner: Option<Vec<Type>>) -> Self {
     Self {
       optional,
-      inner: inner.map(|val| Rc::new(val)),
-      name,
+      shape: TypeShape::Named(name, inner.map(Rc::new)),
+    }
+  }
+
+  /// Builds a `A | B | ...` type, flattening nested unions, dropping
+  /// structurally-equal duplicate members and folding any member's
+  /// `optional` flag into a single trailing `undefined` member. A union of
+  /// one member collapses to that member; an empty union becomes `never`.
+  pub fn union(members: Vec<Type>) -> Self {
+    let mut flat = Vec::new();
+    let mut seen = HashSet::new();
+    let mut optional = false;
+
+    for member in members {
+      optional = optional || member.optional;
+
+      match member.stripped_optional().shape {
+        TypeShape::Union(items) => {
+          for item in items.iter().cloned() {
+            Self::push_unique(&mut flat, &mut seen, item);
+          }
+        }
+        shape => Self::push_unique(&mut flat, &mut seen, Self { optional: false, shape }),
+      }
+    }
+
+    if optional {
+      Self::push_unique(&mut flat, &mut seen, Type::new(Ident::new("undefined"), false, None));
+    }
+
+    match flat.len() {
+      0 => Type::new(Ident::new("never"), false, None),
+      1 => flat.into_iter().next().unwrap(),
+      _ => Self {
+        optional: false,
+        shape: TypeShape::Union(Rc::new(flat)),
+      },
+    }
+  }
+
+  /// Builds a `A & B & ...` type, flattening nested intersections and
+  /// dropping structurally-equal duplicate members. An intersection of one
+  /// member collapses to that member; an empty intersection becomes
+  /// `unknown`.
+  pub fn intersection(members: Vec<Type>) -> Self {
+    let mut flat = Vec::new();
+    let mut seen = HashSet::new();
+
+    for member in members {
+      match member.shape {
+        TypeShape::Intersection(items) => {
+          for item in items.iter().cloned() {
+            Self::push_unique(&mut flat, &mut seen, item);
+          }
+        }
+        _ => Self::push_unique(&mut flat, &mut seen, member),
+      }
+    }
+
+    match flat.len() {
+      0 => Type::new(Ident::new("unknown"), false, None),
+      1 => flat.into_iter().next().unwrap(),
+      _ => Self {
+        optional: false,
+        shape: TypeShape::Intersection(Rc::new(flat)),
+      },
     }
   }
 
@@ -77,10 +346,33 @@ impl Type {
     NonOptionalType { r#type: self }
   }
 
+  fn stripped_optional(&self) -> Type {
+    Self {
+      optional: false,
+      shape: self.shape.clone(),
+    }
+  }
+
+  fn push_unique(flat: &mut Vec<Type>, seen: &mut HashSet<Type>, item: Type) {
+    if seen.insert(item.clone()) {
+      flat.push(item);
+    }
+  }
+
+  /// `|` binds looser than `&`, so a member of an intersection that itself
+  /// renders with a top-level `|` — an explicit `Union` shape, or an
+  /// optional member's trailing `| undefined` — needs parens to keep its
+  /// grouping when joined with ` & `. A union's own members never need
+  /// this: `union()` always strips their `optional` flag, and an
+  /// intersection member nests at *tighter* precedence than `|`, so it
+  /// already groups correctly unparenthesized.
+  fn needs_parens_in_intersection(&self) -> bool {
+    self.optional || matches!(self.shape, TypeShape::Union(_))
+  }
+
   #[inline]
-  fn inner_as_ts(&self) -> String {
-    self
-      .inner
+  fn inner_as_ts(inner: &Option<Rc<Vec<Type>>>) -> String {
+    inner
       .as_ref()
       .map(|val| {
         let types: Vec<String> = val.iter().map(|t| t.as_ts()).collect();
@@ -89,6 +381,91 @@ impl Type {
       })
       .unwrap_or_default()
   }
+
+  fn render_ts(&self) -> String {
+    match &self.shape {
+      TypeShape::Named(name, inner) => {
+        concat_string!(&name.as_ts(), &Self::inner_as_ts(inner))
+      }
+      TypeShape::Union(members) => members
+        .iter()
+        .map(|t| t.as_ts())
+        .collect::<Vec<String>>()
+        .join(" | "),
+      TypeShape::Intersection(members) => members
+        .iter()
+        .map(|t| {
+          let rendered = t.as_ts();
+
+          if t.needs_parens_in_intersection() {
+            format!("({})", rendered)
+          } else {
+            rendered
+          }
+        })
+        .collect::<Vec<String>>()
+        .join(" & "),
+    }
+  }
+
+  #[inline]
+  fn try_inner_as_ts(inner: &Option<Rc<Vec<Type>>>) -> Result<String, GenError> {
+    let types = match inner {
+      Some(val) => val
+        .iter()
+        .map(|t| t.try_as_ts())
+        .collect::<Result<Vec<String>, GenError>>()?,
+      None => return Ok(String::new()),
+    };
+
+    Ok(format!("<{}>", types.join(", ")))
+  }
+
+  fn try_render_ts(&self) -> Result<String, GenError> {
+    match &self.shape {
+      TypeShape::Named(name, inner) => {
+        let name = name.try_as_ts()?;
+        Ok(concat_string!(&name, &Self::try_inner_as_ts(inner)?))
+      }
+      TypeShape::Union(members) => Ok(
+        members
+          .iter()
+          .map(|t| t.try_as_ts())
+          .collect::<Result<Vec<String>, GenError>>()?
+          .join(" | "),
+      ),
+      TypeShape::Intersection(members) => {
+        let rendered = members
+          .iter()
+          .map(|t| {
+            let rendered = t.try_as_ts()?;
+
+            Ok(if t.needs_parens_in_intersection() {
+              format!("({})", rendered)
+            } else {
+              rendered
+            })
+          })
+          .collect::<Result<Vec<String>, GenError>>()?;
+
+        Ok(rendered.join(" & "))
+      }
+    }
+  }
+}
+
+impl PartialEq for Type {
+  fn eq(&self, other: &Self) -> bool {
+    self.shape == other.shape
+  }
+}
+
+impl Eq for Type {}
+
+impl std::hash::Hash for Type {
+  fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+    self.shape.hash(state);
+  }
 }
 
 impl AST for Type {}
@@ -96,7 +473,43 @@ impl AST for Type {}
 impl TSConvertable for Type {
   fn as_ts(&self) -> String {
     let tail = if self.optional { " | undefined" } else { "" };
-    concat_string!(&self.name.as_ts(), &self.inner_as_ts(), tail)
+    concat_string!(&self.render_ts(), tail)
+  }
+}
+
+impl TryTSConvertable for Type {
+  fn try_as_ts(&self) -> Result<String, GenError> {
+    let tail = if self.optional { " | undefined" } else { "" };
+    Ok(concat_string!(&self.try_render_ts()?, tail))
+  }
+}
+
+impl IrSerializable for Type {
+  fn as_ir(&self) -> Value {
+    match &self.shape {
+      TypeShape::Named(name, inner) => {
+        let inner: Option<Vec<Value>> = inner
+          .as_ref()
+          .map(|val| val.iter().map(|t| t.as_ir()).collect());
+
+        json!({
+          "kind": "type",
+          "name": name.as_ir(),
+          "optional": self.optional,
+          "inner": inner,
+        })
+      }
+      TypeShape::Union(members) => json!({
+        "kind": "union",
+        "optional": self.optional,
+        "members": members.iter().map(|t| t.as_ir()).collect::<Vec<Value>>(),
+      }),
+      TypeShape::Intersection(members) => json!({
+        "kind": "intersection",
+        "optional": self.optional,
+        "members": members.iter().map(|t| t.as_ir()).collect::<Vec<Value>>(),
+      }),
+    }
   }
 }
 
@@ -106,6 +519,181 @@ pub(crate) struct NonOptionalType<'a> {
 
 impl TSConvertable for NonOptionalType<'_> {
   fn as_ts(&self) -> String {
-    concat_string!(&self.r#type.name.as_ts(), &self.r#type.inner_as_ts())
+    self.r#type.render_ts()
+  }
+}
+
+impl TryTSConvertable for NonOptionalType<'_> {
+  fn try_as_ts(&self) -> Result<String, GenError> {
+    self.r#type.try_render_ts()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::ast::{Ident, Import, Type};
+  use crate::ts::{TSConvertable, TryTSConvertable};
+
+  #[test]
+  fn should_join_union_members() {
+    let ty = Type::union(vec![
+      Type::new(Ident::new("A"), false, None),
+      Type::new(Ident::new("B"), false, None),
+    ]);
+
+    assert_eq!(ty.as_ts(), "A | B");
+  }
+
+  #[test]
+  fn should_join_intersection_members() {
+    let ty = Type::intersection(vec![
+      Type::new(Ident::new("A"), false, None),
+      Type::new(Ident::new("B"), false, None),
+    ]);
+
+    assert_eq!(ty.as_ts(), "A & B");
+  }
+
+  #[test]
+  fn should_parenthesize_a_union_member_of_an_intersection() {
+    let ty = Type::intersection(vec![
+      Type::union(vec![
+        Type::new(Ident::new("A"), false, None),
+        Type::new(Ident::new("B"), false, None),
+      ]),
+      Type::new(Ident::new("C"), false, None),
+    ]);
+
+    assert_eq!(ty.as_ts(), "(A | B) & C");
+    assert_eq!(ty.try_as_ts().unwrap(), "(A | B) & C");
+  }
+
+  #[test]
+  fn should_parenthesize_an_optional_member_of_an_intersection() {
+    let ty = Type::intersection(vec![
+      Type::new(Ident::new("A"), false, None),
+      Type::new(Ident::new("B"), true, None),
+    ]);
+
+    assert_eq!(ty.as_ts(), "A & (B | undefined)");
+    assert_eq!(ty.try_as_ts().unwrap(), "A & (B | undefined)");
+  }
+
+  #[test]
+  fn should_not_parenthesize_an_intersection_member_of_a_union() {
+    let ty = Type::union(vec![
+      Type::intersection(vec![
+        Type::new(Ident::new("A"), false, None),
+        Type::new(Ident::new("B"), false, None),
+      ]),
+      Type::new(Ident::new("C"), false, None),
+    ]);
+
+    assert_eq!(ty.as_ts(), "A & B | C");
+  }
+
+  #[test]
+  fn should_flatten_nested_unions_and_drop_duplicates() {
+    let ty = Type::union(vec![
+      Type::union(vec![
+        Type::new(Ident::new("A"), false, None),
+        Type::new(Ident::new("B"), false, None),
+      ]),
+      Type::new(Ident::new("B"), false, None),
+      Type::new(Ident::new("C"), false, None),
+    ]);
+
+    assert_eq!(ty.as_ts(), "A | B | C");
+  }
+
+  #[test]
+  fn should_fold_optional_members_into_a_single_undefined_member() {
+    let ty = Type::union(vec![
+      Type::new(Ident::new("A"), true, None),
+      Type::new(Ident::new("B"), false, None),
+    ]);
+
+    assert_eq!(ty.as_ts(), "A | B | undefined");
+  }
+
+  #[test]
+  fn should_collapse_single_member_union_to_the_bare_member() {
+    let ty = Type::union(vec![Type::new(Ident::new("A"), false, None)]);
+
+    assert_eq!(ty.as_ts(), "A");
+  }
+
+  #[test]
+  fn should_treat_empty_union_as_never() {
+    let ty = Type::union(vec![]);
+
+    assert_eq!(ty.as_ts(), "never");
+  }
+
+  #[test]
+  fn should_reject_an_invalid_ident_inside_a_type() {
+    let ty = Type::new(Ident::new("2fa-token"), false, None);
+
+    assert!(ty.try_as_ts().is_err());
+  }
+
+  #[test]
+  fn should_reject_an_invalid_ident_nested_in_a_generic_argument() {
+    let ty = Type::new(
+      Ident::new("Array"),
+      false,
+      Some(vec![Type::new(Ident::new("2fa-token"), false, None)]),
+    );
+
+    assert!(ty.try_as_ts().is_err());
+  }
+
+  #[test]
+  fn should_reject_reserved_words_as_idents() {
+    assert!(Ident::new("class").try_as_ts().is_err());
+  }
+
+  #[test]
+  fn should_reject_idents_not_matching_the_grammar() {
+    assert!(Ident::new("2fa-token").try_as_ts().is_err());
+  }
+
+  #[test]
+  fn should_accept_valid_idents() {
+    assert_eq!(Ident::new("data").try_as_ts().unwrap(), "data");
+  }
+
+  #[test]
+  fn should_reject_imports_with_an_empty_source() {
+    let import = Import::new(Ident::new("Foo"), "");
+
+    assert!(import.try_as_ts().is_err());
+  }
+
+  #[test]
+  fn should_generate_named_imports_with_aliases() {
+    let import = Import::named(
+      vec![
+        (Ident::new("A"), None),
+        (Ident::new("B"), Some(Ident::new("C"))),
+      ],
+      "./foo",
+    );
+
+    assert_eq!(import.as_ts(), "import type { A, B as C } from \"./foo\";");
+  }
+
+  #[test]
+  fn should_generate_namespace_imports() {
+    let import = Import::namespace(Ident::new("ns"), "./foo");
+
+    assert_eq!(import.as_ts(), "import type * as ns from \"./foo\";");
+  }
+
+  #[test]
+  fn should_generate_value_imports() {
+    let import = Import::named(vec![(Ident::new("client"), None)], "./client").into_value();
+
+    assert_eq!(import.as_ts(), "import { client } from \"./client\";");
   }
 }
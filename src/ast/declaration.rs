@@ -1,9 +1,13 @@
 use std::rc::Rc;
 
 use concat_string::concat_string;
+use serde_json::{json, Value};
 
 use crate::ast::{File, FileWithOptions, Ident, Import, Type, AST};
-use crate::ts::TSConvertable;
+use crate::error::GenError;
+use crate::ir::IrSerializable;
+use crate::ts::{TSConvertable, TryTSConvertable};
+use crate::utils::ResultIterator;
 
 #[derive(Clone)]
 pub struct Field {
@@ -32,6 +36,30 @@ impl TSConvertable for Field {
   }
 }
 
+impl TryTSConvertable for Field {
+  fn try_as_ts(&self) -> Result<String, GenError> {
+    let name = self.name.try_as_ts()?;
+    let tail = if self.r#type.is_optional() { "?" } else { "" };
+    let r#type = self
+      .r#type
+      .without_optional()
+      .try_as_ts()
+      .map_err(|e| e.nest(&name))?;
+
+    Ok(format!("  {}{}: {};", name, tail, r#type))
+  }
+}
+
+impl IrSerializable for Field {
+  fn as_ir(&self) -> Value {
+    json!({
+      "kind": "field",
+      "name": self.name.as_ir(),
+      "type": self.r#type.as_ir(),
+    })
+  }
+}
+
 #[derive(Clone)]
 pub struct Declaration {
   fields: Option<Rc<Vec<Field>>>,
@@ -64,6 +92,45 @@ impl TSConvertable for Declaration {
   }
 }
 
+impl TryTSConvertable for Declaration {
+  fn try_as_ts(&self) -> Result<String, GenError> {
+    let name = self.name.try_as_ts()?;
+
+    let fields: Option<Vec<String>> = self
+      .fields
+      .as_ref()
+      .map(|list| {
+        list
+          .iter()
+          .map(Ok)
+          .flat_map_res(|f: &Field| f.try_as_ts().map_err(|e| e.nest(&name)))
+          .collect::<Result<Vec<String>, GenError>>()
+      })
+      .transpose()?;
+
+    Ok(format!(
+      "export default interface {} {{\n{}\n}}",
+      name,
+      fields.map(|list| list.join("\n")).unwrap_or_default()
+    ))
+  }
+}
+
+impl IrSerializable for Declaration {
+  fn as_ir(&self) -> Value {
+    let fields: Option<Vec<Value>> = self
+      .fields
+      .as_ref()
+      .map(|list| list.iter().map(|f| f.as_ir()).collect());
+
+    json!({
+      "kind": "declaration",
+      "name": self.name.as_ir(),
+      "fields": fields,
+    })
+  }
+}
+
 pub type DeclarationFile = File<Declaration>;
 type DeclarationFileWithOptions<'a> = FileWithOptions<'a, Declaration>;
 
@@ -99,15 +166,64 @@ impl TSConvertable for DeclarationFileWithOptions<'_> {
   }
 }
 
+impl TryTSConvertable for DeclarationFileWithOptions<'_> {
+  fn try_as_ts(&self) -> Result<String, GenError> {
+    let imports: Option<Vec<String>> = self
+      .coalesced_imports()
+      .map(|list| {
+        list
+          .iter()
+          .map(Ok)
+          .flat_map_res(|i: &Import| i.try_as_ts())
+          .collect::<Result<Vec<String>, GenError>>()
+      })
+      .transpose()?;
+
+    let content = self.file.content.as_ref().map(|d| d.try_as_ts()).transpose()?;
+
+    Ok(concat_string!(
+      &self
+        .header
+        .as_ref()
+        .map(|val| concat_string!(&val, "\n"))
+        .unwrap_or_default(),
+      &imports
+        .as_ref()
+        .map(|list| concat_string!(&list.join("\n"), "\n\n"))
+        .unwrap_or_default(),
+      &content.as_ref().cloned().unwrap_or_default()
+    ))
+  }
+}
+
+impl IrSerializable for DeclarationFileWithOptions<'_> {
+  fn as_ir(&self) -> Value {
+    let imports: Option<Vec<Value>> = self
+      .file
+      .imports
+      .as_ref()
+      .map(|list| list.iter().map(|i| i.as_ir()).collect());
+
+    json!({
+      "kind": "declarationFile",
+      "imports": imports,
+      "declaration": self.file.content.as_ref().map(|d| d.as_ir()),
+    })
+  }
+}
+
 #[cfg(test)]
 mod tests {
+  use serde_json::json;
+
   use crate::ast::declaration::{Declaration, DeclarationFile, Field};
   use crate::ast::{Ident, Type};
-  use crate::ts::TSConvertable;
+  use crate::error::GenError;
+  use crate::ir::IrSerializable;
+  use crate::ts::{TSConvertable, TryTSConvertable};
 
-  #[test]
-  fn should_generate_code() {
-    let ast = DeclarationFile::new(
+  fn chart_series() -> DeclarationFile {
+    DeclarationFile::new(
       Ident::new("ChartSeries"),
       None,
       Some(Declaration::new(
@@ -127,7 +243,12 @@ mod tests {
           ),
         ]),
       )),
-    );
+    )
+  }
+
+  #[test]
+  fn should_generate_code() {
+    let ast = chart_series();
 
     let code = ast.with_options(Some("/**\n * Some header\n */")).as_ts();
     assert_eq!(
@@ -142,4 +263,75 @@ export default interface ChartSeries {
 }"
     )
   }
+
+  #[test]
+  fn should_generate_ir() {
+    let ast = chart_series();
+
+    let ir = ast.with_options(None).as_ir();
+    assert_eq!(
+      ir,
+      json!({
+        "kind": "declarationFile",
+        "imports": null,
+        "declaration": {
+          "kind": "declaration",
+          "name": "ChartSeries",
+          "fields": [
+            {
+              "kind": "field",
+              "name": "data",
+              "type": {
+                "kind": "type",
+                "name": "Array",
+                "optional": true,
+                "inner": [
+                  {
+                    "kind": "type",
+                    "name": "number",
+                    "optional": true,
+                    "inner": null,
+                  }
+                ],
+              },
+            },
+            {
+              "kind": "field",
+              "name": "name",
+              "type": {
+                "kind": "type",
+                "name": "string",
+                "optional": false,
+                "inner": null,
+              },
+            },
+          ],
+        },
+      })
+    )
+  }
+
+  #[test]
+  fn should_reject_an_invalid_field_name() {
+    let ast = DeclarationFile::new(
+      Ident::new("ChartSeries"),
+      None,
+      Some(Declaration::new(
+        Ident::new("ChartSeries"),
+        Some(vec![Field::new(
+          Ident::new("2fa-token"),
+          Type::new(Ident::new("string"), false, None),
+        )]),
+      )),
+    );
+
+    let err = ast.with_options(None).try_as_ts().unwrap_err();
+    assert_eq!(
+      err,
+      GenError::InvalidIdent {
+        name: "2fa-token".to_string(),
+        path: "ChartSeries.2fa-token".to_string(),
+      }
+    );
+  }
 }
@@ -1,9 +1,13 @@
 use std::rc::Rc;
 
 use concat_string::concat_string;
+use serde_json::{json, Value};
 
 use crate::ast::{File, FileWithOptions, Ident, Import, Type, AST};
-use crate::ts::TSConvertable;
+use crate::error::GenError;
+use crate::ir::IrSerializable;
+use crate::ts::{TSConvertable, TryTSConvertable};
+use crate::utils::ResultIterator;
 
 #[derive(Clone)]
 pub struct Parameter {
@@ -25,6 +29,25 @@ impl TSConvertable for Parameter {
   }
 }
 
+impl TryTSConvertable for Parameter {
+  fn try_as_ts(&self) -> Result<String, GenError> {
+    let name = self.name.try_as_ts()?;
+    let r#type = self.r#type.try_as_ts().map_err(|e| e.nest(&name))?;
+
+    Ok(format!("{}: {}", name, r#type))
+  }
+}
+
+impl IrSerializable for Parameter {
+  fn as_ir(&self) -> Value {
+    json!({
+      "kind": "parameter",
+      "name": self.name.as_ir(),
+      "type": self.r#type.as_ir(),
+    })
+  }
+}
+
 #[derive(Clone)]
 pub struct Method {
   name: Ident,
@@ -51,6 +74,22 @@ impl Method {
 
 impl AST for Method {}
 
+impl IrSerializable for Method {
+  fn as_ir(&self) -> Value {
+    let parameters: Option<Vec<Value>> = self
+      .parameters
+      .as_ref()
+      .map(|list| list.iter().map(|p| p.as_ir()).collect());
+
+    json!({
+      "kind": "method",
+      "name": self.name.as_ir(),
+      "parameters": parameters,
+      "returnType": self.return_type.as_ir(),
+    })
+  }
+}
+
 pub(crate) struct MethodWithOptions<'a> {
   endpoint_name: Ident,
   method: &'a Method,
@@ -89,6 +128,53 @@ impl TSConvertable for MethodWithOptions<'_> {
   }
 }
 
+impl TryTSConvertable for MethodWithOptions<'_> {
+  fn try_as_ts(&self) -> Result<String, GenError> {
+    let name = self.method.name.try_as_ts()?;
+
+    let parameters: Option<Vec<String>> = self
+      .method
+      .parameters
+      .as_ref()
+      .map(|parameters| {
+        parameters
+          .iter()
+          .map(Ok)
+          .flat_map_res(|p: &Parameter| p.try_as_ts().map_err(|e| e.nest(&name)))
+          .collect::<Result<Vec<String>, GenError>>()
+      })
+      .transpose()?;
+
+    let parameter_names: Option<Vec<String>> = self
+      .method
+      .parameters
+      .as_ref()
+      .map(|parameters| parameters.iter().map(|p| p.name.as_ts()).collect());
+
+    let return_type = self
+      .method
+      .return_type
+      .try_as_ts()
+      .map_err(|e| e.nest(&name))?;
+
+    Ok(format!(
+      "function _{}({}): Promise<{}> {{\n  client.call(\"{}\", \"{}\"{});\n}}",
+      name,
+      parameters
+        .as_ref()
+        .map(|list| list.join(", "))
+        .unwrap_or_default(),
+      return_type,
+      self.endpoint_name.as_ts(),
+      name,
+      parameter_names
+        .as_ref()
+        .map(|list| format!(", {{{}}}", list.join(", ")))
+        .unwrap_or_default()
+    ))
+  }
+}
+
 pub type EndpointFile = File<Rc<Vec<Method>>>;
 type EndpointFileWithOptions<'a> = FileWithOptions<'a, Rc<Vec<Method>>>;
 
@@ -146,18 +232,110 @@ impl TSConvertable for EndpointFileWithOptions<'_> {
   }
 }
 
+impl TryTSConvertable for EndpointFileWithOptions<'_> {
+  fn try_as_ts(&self) -> Result<String, GenError> {
+    let endpoint_name = self.file.name.try_as_ts()?;
+
+    let imports: Option<Vec<String>> = self
+      .coalesced_imports()
+      .map(|list| {
+        list
+          .iter()
+          .map(Ok)
+          .flat_map_res(|i: &Import| i.try_as_ts())
+          .collect::<Result<Vec<String>, GenError>>()
+      })
+      .transpose()?;
+
+    let items: Option<Vec<String>> = self
+      .file
+      .content
+      .as_ref()
+      .map(|items| {
+        items
+          .iter()
+          .map(Ok)
+          .flat_map_res(|i: &Method| {
+            i.with_options(self.file.name.clone())
+              .try_as_ts()
+              .map_err(|e| e.nest(&endpoint_name))
+          })
+          .collect::<Result<Vec<String>, GenError>>()
+      })
+      .transpose()?;
+
+    let exports: Option<Vec<String>> = self
+      .file
+      .content
+      .as_ref()
+      .map(|items| items.iter().map(|i| i.name.as_ts()).collect());
+
+    Ok(concat_string!(
+      self
+        .header
+        .as_ref()
+        .map(|val| concat_string!(val, "\n"))
+        .unwrap_or_default(),
+      imports
+        .as_ref()
+        .map(|val| concat_string!(val.join("\n"), "\n\n"))
+        .unwrap_or_default(),
+      items
+        .as_ref()
+        .map(|i| concat_string!(i.join("\n\n"), "\n\n"))
+        .unwrap_or_default(),
+      exports
+        .as_ref()
+        .map(|e| {
+          let exports: Vec<String> = e
+            .iter()
+            .map(|name| format!("  _{n} as {n},", n = name))
+            .collect();
+
+          format!("export {{\n{}\n}};", exports.join("\n"))
+        })
+        .unwrap_or_default()
+    ))
+  }
+}
+
+impl IrSerializable for EndpointFileWithOptions<'_> {
+  fn as_ir(&self) -> Value {
+    let imports: Option<Vec<Value>> = self
+      .file
+      .imports
+      .as_ref()
+      .map(|list| list.iter().map(|i| i.as_ir()).collect());
+
+    let methods: Option<Vec<Value>> = self
+      .file
+      .content
+      .as_ref()
+      .map(|list| list.iter().map(|m| m.as_ir()).collect());
+
+    json!({
+      "kind": "endpointFile",
+      "imports": imports,
+      "methods": methods,
+    })
+  }
+}
+
 #[cfg(test)]
 mod tests {
+  use serde_json::json;
+
   use crate::ast::{EndpointFile, Ident, Import, Method, Parameter, Type};
-  use crate::ts::TSConvertable;
+  use crate::error::GenError;
+  use crate::ir::IrSerializable;
+  use crate::ts::{TSConvertable, TryTSConvertable};
 
-  #[test]
-  fn should_generate_code() {
+  fn dashboard_endpoint() -> EndpointFile {
     let health_grid_item_struct_ident = Ident::new("HealthGridItem");
     let chart_series_struct_ident = Ident::new("ChartsSeries");
     let array_ident = Ident::new("Array");
 
-    let ast = EndpointFile::new(
+    EndpointFile::new(
       Ident::new("DashboardEndpoint"),
       Some(vec![
         Import::new(
@@ -206,7 +384,12 @@ mod tests {
           ),
         ),
       ]),
-    );
+    )
+  }
+
+  #[test]
+  fn should_generate_code() {
+    let ast = dashboard_endpoint();
 
     let code = ast.with_options(Some("/**\n * Some header\n */")).as_ts();
     assert_eq!(
@@ -232,4 +415,155 @@ export {
 };"
     )
   }
+
+  #[test]
+  fn should_generate_ir() {
+    let ast = dashboard_endpoint();
+
+    let ir = ast.with_options(None).as_ir();
+    assert_eq!(
+      ir,
+      json!({
+        "kind": "endpointFile",
+        "imports": [
+          {
+            "kind": "import",
+            "symbol": "ChartsSeries",
+            "source": "./com/example/application/views/dashboard/ChartSeries",
+          },
+          {
+            "kind": "import",
+            "symbol": "HealthGridItem",
+            "source": "./com/example/application/views/dashboard/HealthGridItem",
+          },
+        ],
+        "methods": [
+          {
+            "kind": "method",
+            "name": "healthGridItems",
+            "parameters": null,
+            "returnType": {
+              "kind": "type",
+              "name": "Array",
+              "optional": false,
+              "inner": [
+                {
+                  "kind": "type",
+                  "name": "HealthGridItem",
+                  "optional": false,
+                  "inner": null,
+                }
+              ],
+            },
+          },
+          {
+            "kind": "method",
+            "name": "monthlyVisitorSeries",
+            "parameters": [
+              {
+                "kind": "parameter",
+                "name": "id",
+                "type": {
+                  "kind": "type",
+                  "name": "number",
+                  "optional": true,
+                  "inner": null,
+                },
+              },
+              {
+                "kind": "parameter",
+                "name": "optional",
+                "type": {
+                  "kind": "type",
+                  "name": "boolean",
+                  "optional": false,
+                  "inner": null,
+                },
+              },
+            ],
+            "returnType": {
+              "kind": "type",
+              "name": "Array",
+              "optional": true,
+              "inner": [
+                {
+                  "kind": "type",
+                  "name": "ChartsSeries",
+                  "optional": true,
+                  "inner": null,
+                }
+              ],
+            },
+          },
+        ],
+      })
+    )
+  }
+
+  #[test]
+  fn should_report_the_full_path_of_an_invalid_parameter_name() {
+    let ast = EndpointFile::new(
+      Ident::new("DashboardEndpoint"),
+      None,
+      Some(vec![Method::new(
+        Ident::new("monthlyVisitorSeries"),
+        Some(vec![Parameter::new(
+          Ident::new("2fa"),
+          Type::new(Ident::new("number"), false, None),
+        )]),
+        Type::new(Ident::new("void"), false, None),
+      )]),
+    );
+
+    let err = ast.with_options(None).try_as_ts().unwrap_err();
+    assert_eq!(
+      err,
+      GenError::InvalidIdent {
+        name: "2fa".to_string(),
+        path: "DashboardEndpoint.monthlyVisitorSeries.2fa".to_string(),
+      }
+    );
+  }
+
+  #[test]
+  fn should_reject_an_invalid_return_type_ident() {
+    let ast = EndpointFile::new(
+      Ident::new("DashboardEndpoint"),
+      None,
+      Some(vec![Method::new(
+        Ident::new("monthlyVisitorSeries"),
+        None,
+        Type::new(Ident::new("2fa-token"), false, None),
+      )]),
+    );
+
+    let err = ast.with_options(None).try_as_ts().unwrap_err();
+    assert_eq!(
+      err,
+      GenError::InvalidIdent {
+        name: "2fa-token".to_string(),
+        path: "DashboardEndpoint.monthlyVisitorSeries.2fa-token".to_string(),
+      }
+    );
+  }
+
+  #[test]
+  fn should_coalesce_named_imports_from_the_same_source_and_emit_a_client_preamble() {
+    let ast = EndpointFile::new(
+      Ident::new("DashboardEndpoint"),
+      Some(vec![
+        Import::named(vec![(Ident::new("client"), None)], "./connect-client.default")
+          .into_value(),
+        Import::named(vec![(Ident::new("A"), None)], "./models"),
+        Import::named(vec![(Ident::new("B"), None)], "./models"),
+      ]),
+      None,
+    );
+
+    let code = ast.with_options(None).as_ts();
+    assert_eq!(
+      code,
+      "import { client } from \"./connect-client.default\";\nimport type { A, B } from \"./models\";\n\n"
+    )
+  }
 }
@@ -34,13 +34,36 @@ pub(crate) struct FileWithOptions<'a, T> {
 impl<T> FileWithOptions<'_, T> {
   #[inline]
   pub(crate) fn imports_as_ts(&self) -> Option<String> {
-    let imports: Option<Vec<String>> = self
-      .file
-      .imports
-      .as_ref()
-      .map(|list| list.iter().map(|i| i.as_ts()).collect());
+    let coalesced = self.coalesced_imports()?;
 
-    imports.map(|list| list.join("\n"))
+    Some(
+      coalesced
+        .iter()
+        .map(|i| i.as_ts())
+        .collect::<Vec<String>>()
+        .join("\n"),
+    )
+  }
+
+  /// Merges named imports that share a source and a type/value-ness into a
+  /// single `import type { A, B } from "..."` statement, the way a
+  /// hand-written client would group them.
+  pub(crate) fn coalesced_imports(&self) -> Option<Vec<Import>> {
+    let imports = self.file.imports.as_ref()?;
+
+    let mut coalesced: Vec<Import> = Vec::new();
+
+    for import in imports.iter() {
+      match coalesced
+        .iter_mut()
+        .find(|existing| existing.can_coalesce_with(import))
+      {
+        Some(existing) => existing.coalesce(import),
+        None => coalesced.push(import.clone()),
+      }
+    }
+
+    Some(coalesced)
   }
 }
 
@@ -0,0 +1,25 @@
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum GenError {
+  #[error("\"{name}\" is not a valid TypeScript identifier ({path})")]
+  InvalidIdent { name: String, path: String },
+  #[error("import source must not be empty ({path})")]
+  EmptyImportSource { path: String },
+}
+
+impl GenError {
+  /// Prepends `segment` to the error's path, turning e.g. `id` into
+  /// `monthlyVisitorSeries.id` as the error bubbles up through the AST.
+  pub(crate) fn nest(self, segment: &str) -> Self {
+    match self {
+      GenError::InvalidIdent { name, path } => GenError::InvalidIdent {
+        name,
+        path: format!("{}.{}", segment, path),
+      },
+      GenError::EmptyImportSource { path } => GenError::EmptyImportSource {
+        path: format!("{}.{}", segment, path),
+      },
+    }
+  }
+}
@@ -0,0 +1,7 @@
+use serde_json::Value;
+
+/// Mirrors `TSConvertable`, but emits a stable, machine-readable JSON
+/// intermediate representation instead of a TypeScript source string.
+pub trait IrSerializable {
+  fn as_ir(&self) -> Value;
+}
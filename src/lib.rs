@@ -0,0 +1,5 @@
+pub mod ast;
+pub mod error;
+pub mod ir;
+pub mod ts;
+mod utils;
@@ -0,0 +1,12 @@
+use crate::error::GenError;
+
+pub trait TSConvertable {
+  fn as_ts(&self) -> String;
+}
+
+/// Mirrors `TSConvertable`, but validates as it walks so that an invalid
+/// name fails generation loudly instead of being emitted as broken
+/// TypeScript.
+pub trait TryTSConvertable {
+  fn try_as_ts(&self) -> Result<String, GenError>;
+}
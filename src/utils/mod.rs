@@ -0,0 +1,3 @@
+mod result_iter;
+
+pub(crate) use result_iter::ResultIterator;
@@ -1,6 +1,3 @@
-use core::slice::Iter;
-use std::iter::Map;
-
 pub(crate) trait ResultIterator: Iterator {
   fn flat_map_res<T, R, E, F>(self, f: F) -> FlatMapRes<Self, F>
   where
@@ -13,7 +10,7 @@ pub(crate) trait ResultIterator: Iterator {
 
 impl<T: ?Sized> ResultIterator for T where T: Iterator {}
 
-pub struct FlatMapRes<I, F> {
+pub(crate) struct FlatMapRes<I, F> {
   iter: I,
   f: F,
 }